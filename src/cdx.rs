@@ -0,0 +1,107 @@
+//! CDX-style indexing and random-access seeking
+//!
+//! Leverages [WarcReader::sum](crate::WarcReader::sum) to record, per record, the
+//! byte offset it starts at, so a caller can build a CDX index once and later
+//! jump straight to a record instead of re-parsing everything before it.
+
+use std::io::{BufRead, Seek, SeekFrom};
+
+use crate::{WarcError, WarcReader, WarcRecord};
+
+/// A single CDX-style index entry
+///
+/// Describes where one record lives in the underlying file, plus the handful
+/// of headers commonly used to look records up (`WARC-Type`,
+/// `WARC-Target-URI`, `WARC-Record-ID`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdxEntry {
+    /// Byte offset of the record's `version` line from the start of the stream
+    pub offset: usize,
+    /// Total length of the record in bytes, version line through the trailing `\r\n\r\n`
+    pub length: usize,
+    /// `WARC-Type` header value, if present
+    pub record_type: Option<String>,
+    /// `WARC-Target-URI` header value, if present
+    pub uri: Option<String>,
+    /// `WARC-Record-ID` header value, if present
+    pub id: Option<String>,
+}
+
+impl<R: BufRead + Seek> WarcReader<R> {
+    /// Reposition the reader at a byte offset, such as one recorded in a [CdxEntry]
+    ///
+    /// Resets the reader's internal error state so iteration can resume after a
+    /// seek, even if a previous pass hit a malformed or incomplete record.
+    pub fn seek_to(&mut self, offset: usize) -> std::io::Result<()> {
+        self.read.seek(SeekFrom::Start(offset as u64))?;
+        self.sum = offset;
+        self.valid_state = true;
+        Ok(())
+    }
+
+    /// Scan the whole stream from the current position and build a CDX index
+    ///
+    /// Stops cleanly at EOF. A malformed or truncated record partway through
+    /// the stream is surfaced as `Err` rather than silently truncating the
+    /// index; the entries collected before the error are not returned.
+    pub fn index(&mut self) -> Result<Vec<CdxEntry>, WarcError> {
+        let mut entries = Vec::new();
+
+        loop {
+            let offset = self.sum;
+            match WarcRecord::parse(&mut self.read, &mut self.sum) {
+                Ok(record) => {
+                    entries.push(CdxEntry {
+                        offset,
+                        length: self.sum - offset,
+                        record_type: record.header.get(&"WARC-Type".into()).cloned(),
+                        uri: record.header.get(&"WARC-Target-URI".into()).cloned(),
+                        id: record.header.get(&"WARC-Record-ID".into()).cloned(),
+                    });
+                }
+                Err(WarcError::EOF) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const TWO_RECORDS: &[u8] = b"WARC/1.1\r\nWARC-Type: warcinfo\r\nWARC-Target-URI: http://example.com/a\r\nContent-Length: 0\r\n\r\n\r\n\r\nWARC/1.1\r\nWARC-Type: request\r\nWARC-Target-URI: http://example.com/b\r\nContent-Length: 0\r\n\r\n\r\n\r\n";
+
+    #[test]
+    fn index_and_seek() {
+        let mut warc = WarcReader::new(Cursor::new(TWO_RECORDS.to_vec()));
+
+        let entries = warc.index().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].record_type, Some("warcinfo".to_string()));
+        assert_eq!(entries[1].record_type, Some("request".to_string()));
+        assert_eq!(
+            entries[1].uri,
+            Some("http://example.com/b".to_string())
+        );
+
+        warc.seek_to(entries[1].offset).unwrap();
+        let record = warc.next().unwrap().unwrap();
+        assert_eq!(
+            record.header.get(&"WARC-Type".into()),
+            Some(&"request".into())
+        );
+    }
+
+    #[test]
+    fn index_surfaces_errors_instead_of_truncating() {
+        // Content-Length claims more bytes than are actually present
+        let data = b"WARC/1.1\r\nWARC-Type: response\r\nContent-Length: 10\r\n\r\n".to_vec();
+        let mut warc = WarcReader::new(Cursor::new(data));
+
+        assert!(warc.index().is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Transparent per-record gzip decompression for `.warc.gz`
+//!
+//! WARC archives found in the wild are almost always stored as a concatenated
+//! gzip stream with one independent gzip member per record, which is exactly
+//! what lets a CDX offset point straight at a compressed block without having
+//! to decompress anything before it first. [GzWarcRead] decodes one member at
+//! a time and transparently starts the next as soon as the current one ends,
+//! so [WarcReader] can iterate `.warc.gz` input exactly like plain `.warc`.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+use flate2::bufread::GzDecoder;
+
+use crate::WarcReader;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+enum GzState<R> {
+    /// between members, not yet decoding
+    Idle(R),
+    Decoding(GzDecoder<R>),
+    Done,
+}
+
+/// Adapts a gzip-compressed, multi-member `.warc.gz` stream into plain [BufRead]
+///
+/// Each gzip member is decoded in turn. Running out of bytes in one member is
+/// not treated as EOF of the whole stream: once a member's decoder returns
+/// zero bytes, the underlying reader is checked for the next member's magic
+/// bytes before giving up.
+pub struct GzWarcRead<R> {
+    state: GzState<R>,
+}
+
+impl<R: BufRead> GzWarcRead<R> {
+    /// Wrap a (possibly multi-member) gzip stream
+    pub fn new(read: R) -> Self {
+        Self {
+            state: GzState::Idle(read),
+        }
+    }
+
+    // make sure `state` is `Decoding` (starting the next member if one is
+    // available) or `Done`; returns false once there is nothing left to read
+    fn ensure_decoder(&mut self) -> io::Result<bool> {
+        if matches!(self.state, GzState::Idle(_)) {
+            let mut read = match std::mem::replace(&mut self.state, GzState::Done) {
+                GzState::Idle(read) => read,
+                _ => unreachable!(),
+            };
+            let buf = read.fill_buf()?;
+
+            if buf.is_empty() {
+                // clean EOF between members
+                self.state = GzState::Done;
+                return Ok(false);
+            }
+
+            if buf.len() < GZIP_MAGIC.len() || buf[..GZIP_MAGIC.len()] != GZIP_MAGIC {
+                self.state = GzState::Done;
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "expected gzip magic bytes at start of member",
+                ));
+            }
+
+            self.state = GzState::Decoding(GzDecoder::new(read));
+        }
+
+        Ok(!matches!(self.state, GzState::Done))
+    }
+}
+
+impl<R: BufRead> Read for GzWarcRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if !self.ensure_decoder()? {
+                return Ok(0);
+            }
+
+            let decoder = match &mut self.state {
+                GzState::Decoding(decoder) => decoder,
+                _ => unreachable!(),
+            };
+
+            let n = decoder.read(buf)?;
+            if n > 0 {
+                return Ok(n);
+            }
+
+            // member exhausted: reclaim the underlying reader and look for
+            // the next one instead of reporting EOF
+            if let GzState::Decoding(decoder) = std::mem::replace(&mut self.state, GzState::Done) {
+                self.state = GzState::Idle(decoder.into_inner());
+            }
+        }
+    }
+}
+
+impl<R: BufRead> WarcReader<BufReader<GzWarcRead<R>>> {
+    /// Create a WarcReader that transparently decompresses a multi-member `.warc.gz` stream
+    pub fn with_gzip(read: R) -> Self {
+        WarcReader::new(BufReader::new(GzWarcRead::new(read)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn gzip_member(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn reads_full_member_across_multiple_read_calls() {
+        let payload = vec![b'x'; 8192]; // bigger than any single small read
+        let compressed = gzip_member(&payload);
+
+        let mut gz = GzWarcRead::new(&compressed[..]);
+        let mut out = Vec::new();
+        let mut buf = [0u8; 16]; // force many small reads within one member
+        loop {
+            let n = gz.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+
+        assert_eq!(out, payload);
+    }
+
+    #[test]
+    fn iterates_concatenated_members() {
+        let mut concatenated = gzip_member(b"first");
+        concatenated.extend(gzip_member(b"second"));
+
+        let mut gz = GzWarcRead::new(&concatenated[..]);
+        let mut out = Vec::new();
+        gz.read_to_end(&mut out).unwrap();
+
+        assert_eq!(out, b"firstsecond");
+    }
+}
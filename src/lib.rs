@@ -30,14 +30,76 @@
 //! }
 //! ```
 
+#![cfg_attr(feature = "no_std", no_std)]
+
+#[cfg(feature = "no_std")]
+extern crate alloc;
+
+#[cfg(not(feature = "no_std"))]
 use std::collections::HashMap;
+#[cfg(feature = "no_std")]
+use alloc::collections::BTreeMap as HashMap;
+
+#[cfg(not(feature = "no_std"))]
+use std::string::String;
+#[cfg(feature = "no_std")]
+use alloc::string::String;
+#[cfg(feature = "no_std")]
+use alloc::string::ToString;
+#[cfg(feature = "no_std")]
+use alloc::vec;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "no_std"))]
 use std::io::BufRead;
+#[cfg(feature = "no_std")]
+use no_std_io::BufRead;
+
+/// The IO error type wrapped by [WarcError::IO]
+///
+/// This is [std::io::Error] normally, or a minimal `core`/`alloc`-only
+/// stand-in when the `no_std` feature is enabled.
+#[cfg(not(feature = "no_std"))]
+pub type IoError = std::io::Error;
+#[cfg(feature = "no_std")]
+pub type IoError = no_std_io::Error;
+
+#[cfg(not(feature = "no_std"))]
+mod cdx;
+#[cfg(all(feature = "flate2", not(feature = "no_std")))]
+mod gzip;
+#[cfg(feature = "no_std")]
+mod no_std_io;
+#[cfg(not(feature = "no_std"))]
+mod streaming;
+#[cfg(not(feature = "no_std"))]
+mod writer;
+#[cfg(not(feature = "no_std"))]
+pub use cdx::CdxEntry;
+#[cfg(all(feature = "flate2", not(feature = "no_std")))]
+pub use gzip::GzWarcRead;
+#[cfg(not(feature = "no_std"))]
+pub use streaming::{StreamingContent, WarcRecordHead};
+#[cfg(not(feature = "no_std"))]
+pub use writer::WarcWriter;
 
 // trim a string in place (no (re)allocations)
 fn rtrim(s: &mut String) {
     s.truncate(s.trim_end().len());
 }
 
+// a fresh, empty header map; pre-sized to avoid reallocating for the common
+// case of <= 16 header fields where the backing map supports it
+#[cfg(not(feature = "no_std"))]
+fn new_header_map() -> HashMap<CaseString, String> {
+    HashMap::with_capacity(16)
+}
+#[cfg(feature = "no_std")]
+fn new_header_map() -> HashMap<CaseString, String> {
+    HashMap::new()
+}
+
 /// Case insensitive string
 ///
 /// ```
@@ -51,13 +113,54 @@ fn rtrim(s: &mut String) {
 ///
 /// assert_eq!(s1, s2);
 /// ```
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(Debug)]
 pub struct CaseString {
     inner: String,
+    // casing as originally given, kept around purely so round-tripping a
+    // record back through WarcWriter preserves canonical header names
+    // (`WARC-Type`, not `warc-type`), without weakening case-insensitive
+    // equality/hashing, which only ever looks at `inner`
+    original: String,
 }
 impl CaseString {
-    pub fn to_string(self) -> String {
-        self.into()
+    /// Borrow the lower-cased inner string
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// Borrow the string as originally given, before lower-casing
+    pub fn as_original_str(&self) -> &str {
+        &self.original
+    }
+}
+
+impl core::fmt::Display for CaseString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+
+impl PartialEq for CaseString {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+impl Eq for CaseString {}
+
+impl core::hash::Hash for CaseString {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl PartialOrd for CaseString {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for CaseString {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.inner.cmp(&other.inner)
     }
 }
 
@@ -68,10 +171,12 @@ impl PartialEq<String> for CaseString {
 }
 
 impl From<String> for CaseString {
-    fn from(mut s: String) -> Self {
-        s.make_ascii_lowercase();
+    fn from(s: String) -> Self {
+        let original = s.clone();
+        let mut inner = s;
+        inner.make_ascii_lowercase();
 
-        CaseString { inner: s }
+        CaseString { inner, original }
     }
 }
 impl From<&str> for CaseString {
@@ -80,9 +185,9 @@ impl From<&str> for CaseString {
     }
 }
 
-impl Into<String> for CaseString {
-    fn into(self) -> String {
-        self.inner
+impl From<CaseString> for String {
+    fn from(val: CaseString) -> Self {
+        val.inner
     }
 }
 
@@ -109,7 +214,7 @@ impl Into<String> for CaseString {
 ///
 /// let mut data = &include_bytes!("test.warc")[..];
 ///
-/// let item = WarcRecord::parse(&mut data).unwrap();
+/// let item = WarcRecord::parse(&mut data, &mut 0).unwrap();
 ///
 /// assert_eq!(item.version, "WARC/1.1");
 ///
@@ -129,133 +234,144 @@ pub struct WarcRecord {
     pub content: Vec<u8>,
 }
 
-impl WarcRecord {
-    pub fn parse(mut read: impl BufRead, sum: &mut usize) -> Result<Self, WarcError> {
-        let mut version = String::new();
-        let mut version_len = 0;
-        let mut headers_len = 0;
-        match read.read_line(&mut version) {
+// Parses the `version` line and header map shared by `parse` and
+// `parse_streaming`, leaving the reader positioned right at the content
+// block. Returns the parsed `Content-Length` alongside the head so callers
+// don't have to re-extract it.
+pub(crate) fn parse_head(
+    read: &mut impl BufRead,
+    sum: &mut usize,
+) -> Result<(String, HashMap<CaseString, String>, usize), WarcError> {
+    let mut version = String::new();
+    let version_len;
+    let mut headers_len = 0;
+    match read.read_line(&mut version) {
+        Err(io) => {
+            // println!("{:?}", 1);
+            return Err(WarcError::IO(io))
+        },
+        Ok(pos) => {
+            *sum += pos;
+            version_len = pos;
+        }
+    };
+
+    if version.is_empty() {
+       // println!("{:?}", 2);
+        return Err(WarcError::EOF);
+    }
+
+    rtrim(&mut version);
+
+    if !version.starts_with("WARC/1.") {
+        *sum -= version_len;
+        // println!("{:?}", 3);
+        return Err(WarcError::Malformed(String::from("Unknown WARC version")));
+    }
+
+    let mut header = new_header_map();
+
+    let mut continuation: Option<(CaseString, String)> = None;
+    loop {
+        let mut line_buf = String::new();
+        match read.read_line(&mut line_buf) {
             Err(io) => {
-                // println!("{:?}", 1);   
+                *sum -= version_len + headers_len;
+                // println!("{:?}", 4);
                 return Err(WarcError::IO(io))
             },
             Ok(pos) => {
-                *sum = *sum + pos;
-                version_len = pos;
+                *sum += pos;
+                headers_len += pos;
             }
-        };
-
-        if version.is_empty() {
-           // println!("{:?}", 2);    
-            return Err(WarcError::EOF);
         }
 
-        rtrim(&mut version);
-
-        if !version.starts_with("WARC/1.") {
-            *sum = *sum - version_len;
-            // println!("{:?}", 3);   
-            return Err(WarcError::Malformed(String::from("Unknown WARC version")));
+        if &line_buf == "\r\n" {
+            break;
         }
 
-        let mut header = HashMap::<CaseString, String>::with_capacity(16); // no allocations if <= 16 header fields
-
-        let mut continuation: Option<(CaseString, String)> = None;
-        loop {
-            let mut line_buf = String::new();
-            match read.read_line(&mut line_buf) {
-                Err(io) => {
-                    *sum = *sum - version_len - headers_len;
-                    // println!("{:?}", 4);   
-                    return Err(WarcError::IO(io))
-                },
-                Ok(pos) => {
-                    *sum = *sum + pos;
-                    headers_len += pos;
-                }   
-            }
+        rtrim(&mut line_buf);
 
-            if &line_buf == "\r\n" {
-                break;
+        if line_buf.starts_with(' ') || line_buf.starts_with('\t') {
+            if let Some(keyval) = &mut continuation {
+                keyval.1.push('\n');
+                keyval.1.push_str(line_buf.trim());
+            } else {
+                *sum -= version_len + headers_len;
+                // println!("{:?}", 5);
+                return Err(WarcError::Malformed(String::from("Invalid header block")));
+            }
+        } else {
+            if let Some((key, value)) = continuation.take() {
+                header.insert(key, value);
             }
 
-            rtrim(&mut line_buf);
-
-            if line_buf.starts_with(' ') || line_buf.starts_with('\t') {
-                if let Some(keyval) = &mut continuation {
-                    keyval.1.push('\n');
-                    keyval.1.push_str(line_buf.trim());
-                } else {
-                    *sum = *sum - version_len - headers_len;
-                    // println!("{:?}", 5);   
-                    return Err(WarcError::Malformed(String::from("Invalid header block")));
-                }
+            if let Some(semi) = line_buf.find(':') {
+                let value = line_buf.split_off(semi + 1).trim().to_string();
+                line_buf.pop(); // eat colon
+                rtrim(&mut line_buf);
+
+                continuation = Some((line_buf.into(), value));
             } else {
-                if let Some((key, value)) = std::mem::replace(&mut continuation, None) {
-                    header.insert(key, value);
-                }
-
-                if let Some(semi) = line_buf.find(':') {
-                    let value = line_buf.split_off(semi + 1).trim().to_string();
-                    line_buf.pop(); // eat colon
-                    rtrim(&mut line_buf);
-
-                    continuation = Some((line_buf.into(), value));
-                } else {
-                    *sum = *sum - version_len - headers_len;
-                    // println!("{:?}", 6);   
-                    return Err(WarcError::Malformed(String::from("Invalid header field")));
-                }
+                *sum -= version_len + headers_len;
+                // println!("{:?}", 6);
+                return Err(WarcError::Malformed(String::from("Invalid header field")));
             }
         }
+    }
 
-        // insert leftover continuation
-        if let Some((key, value)) = continuation {
-            header.insert(key, value);
-        }
+    // insert leftover continuation
+    if let Some((key, value)) = continuation {
+        header.insert(key, value);
+    }
 
-        let content_len = header.get(&"Content-Length".into());
-        if content_len.is_none() {
-            *sum = *sum - version_len - headers_len;
-            // println!("{:?}", 7);   
-            return Err(WarcError::Malformed(String::from(
-                "Content-Length is missing",
-            )));
-        }
+    let content_len = header.get(&"Content-Length".into());
+    if content_len.is_none() {
+        *sum -= version_len + headers_len;
+        // println!("{:?}", 7);
+        return Err(WarcError::Malformed(String::from(
+            "Content-Length is missing",
+        )));
+    }
 
-        let content_len = content_len.unwrap().parse::<usize>();
-        if content_len.is_err() {
-            *sum = *sum - version_len - headers_len;
-            // println!("{:?}", 8);   
-            return Err(WarcError::Malformed(String::from(
-                "Content-Length is not a number",
-            )));
-        }
+    let content_len = content_len.unwrap().parse::<usize>();
+    if content_len.is_err() {
+        *sum -= version_len + headers_len;
+        // println!("{:?}", 8);
+        return Err(WarcError::Malformed(String::from(
+            "Content-Length is not a number",
+        )));
+    }
+    let content_len = content_len.unwrap();
+
+    Ok((version, header, content_len))
+}
+
+impl WarcRecord {
+    pub fn parse(mut read: impl BufRead, sum: &mut usize) -> Result<Self, WarcError> {
+        let head_start = *sum;
+        let (version, header, content_len) = parse_head(&mut read, sum)?;
+        let headers_len = *sum - head_start;
 
-        let content_len = content_len.unwrap();
         let mut content = vec![0; content_len];
-        
-        if let Err(io) = read.read_exact(&mut content) {
 
-            *sum = *sum - version_len - headers_len;
-            // println!("{:?}", 8);   
+        if let Err(io) = read.read_exact(&mut content) {
+            *sum -= headers_len;
             return Err(WarcError::IO(io));
         } else {
-            *sum = *sum + content_len;
+            *sum += content_len;
         }
 
         let mut linefeed = [0u8; 4];
-        
+
         if let Err(io) = read.read_exact(&mut linefeed) {
-            *sum = *sum - version_len - headers_len - content_len;
-            // println!("{:?}", 9);   
+            *sum -= headers_len + content_len;
             return Err(WarcError::IO(io));
         } else {
-            *sum = *sum + 4;
+            *sum += 4;
         }
         if linefeed != [13, 10, 13, 10] {
-            *sum = *sum - version_len - headers_len - content_len;
-            // println!("{:?}", 10);   
+            *sum -= headers_len + content_len;
             return Err(WarcError::Malformed(String::from(
                 "No double linefeed after record content",
             )));
@@ -275,7 +391,7 @@ impl WarcRecord {
 #[derive(Debug)]
 pub enum WarcError {
     Malformed(String),
-    IO(std::io::Error),
+    IO(IoError),
     EOF,
 }
 
@@ -0,0 +1,166 @@
+//! Minimal `core`/`alloc`-only stand-ins for `std::io::{Read, BufRead}`
+//!
+//! Only used when the `no_std` feature is enabled. Mirrors the subset of the
+//! `std::io` surface [WarcRecord::parse](crate::WarcRecord::parse) actually
+//! needs, so the parser can run against an embedded byte source with no
+//! allocator-independent runtime available.
+
+use alloc::string::String;
+use core::fmt;
+
+/// Stand-in for [std::io::Error] carrying just a short message
+#[derive(Debug)]
+pub struct Error(pub &'static str);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Stand-in for [std::io::Read]
+pub trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), Error> {
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => return Err(Error("failed to fill whole buffer")),
+                n => buf = &mut buf[n..],
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stand-in for [std::io::BufRead]
+pub trait BufRead: Read {
+    fn fill_buf(&mut self) -> Result<&[u8], Error>;
+    fn consume(&mut self, amt: usize);
+
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, Error> {
+        let mut read = 0;
+        loop {
+            let (done, used) = {
+                let available = self.fill_buf()?;
+                match available.iter().position(|&b| b == b'\n') {
+                    Some(i) => {
+                        buf.push_str(
+                            core::str::from_utf8(&available[..=i])
+                                .map_err(|_| Error("stream did not contain valid UTF-8"))?,
+                        );
+                        (true, i + 1)
+                    }
+                    None => {
+                        if available.is_empty() {
+                            (true, 0)
+                        } else {
+                            buf.push_str(
+                                core::str::from_utf8(available)
+                                    .map_err(|_| Error("stream did not contain valid UTF-8"))?,
+                            );
+                            (false, available.len())
+                        }
+                    }
+                }
+            };
+            self.consume(used);
+            read += used;
+            if done || used == 0 {
+                return Ok(read);
+            }
+        }
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        (**self).read(buf)
+    }
+}
+
+impl<R: BufRead + ?Sized> BufRead for &mut R {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+}
+
+/// A byte slice is the canonical `no_std` record source
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let n = buf.len().min(self.len());
+        buf[..n].copy_from_slice(&self[..n]);
+        *self = &self[n..];
+        Ok(n)
+    }
+}
+
+impl BufRead for &[u8] {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        Ok(*self)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt.min(self.len())..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn read_exact_drains_a_slice_across_multiple_calls() {
+        let mut data: &[u8] = b"hello world";
+
+        let mut first = [0u8; 5];
+        data.read_exact(&mut first).unwrap();
+        assert_eq!(&first, b"hello");
+
+        let mut rest = [0u8; 6];
+        data.read_exact(&mut rest).unwrap();
+        assert_eq!(&rest, b" world");
+
+        // nothing left: even an empty read is fine, but asking for more fails
+        let mut too_much = [0u8; 1];
+        assert!(data.read_exact(&mut too_much).is_err());
+    }
+
+    #[test]
+    fn read_line_splits_on_newlines_and_reports_trailing_data_without_one() {
+        let mut data: &[u8] = b"first\nsecond\nthird";
+
+        let mut line = String::new();
+        let n = data.read_line(&mut line).unwrap();
+        assert_eq!(n, 6);
+        assert_eq!(line, "first\n");
+
+        line.clear();
+        data.read_line(&mut line).unwrap();
+        assert_eq!(line, "second\n");
+
+        // no trailing '\n' before EOF: still returned, just without one
+        line.clear();
+        let n = data.read_line(&mut line).unwrap();
+        assert_eq!(n, 5);
+        assert_eq!(line, "third");
+
+        // now truly empty
+        line.clear();
+        let n = data.read_line(&mut line).unwrap();
+        assert_eq!(n, 0);
+        assert_eq!(line, "".to_string());
+    }
+
+    #[test]
+    fn consume_saturates_instead_of_panicking_past_the_end() {
+        let mut data: &[u8] = b"abc";
+        data.consume(10);
+        assert_eq!(data.fill_buf().unwrap(), b"");
+    }
+}
@@ -0,0 +1,146 @@
+//! Streaming access to record content
+//!
+//! [WarcRecord::parse](crate::WarcRecord::parse) materializes the whole
+//! content block into a `Vec<u8>`, which is wasteful for large payloads (HTTP
+//! responses can run into the hundreds of MiB). [WarcRecord::parse_streaming]
+//! parses the `version` and `header` as usual (sharing the same head-parsing
+//! logic as [WarcRecord::parse]) but hands back a [StreamingContent] handle
+//! instead, so a caller can pipe the payload straight to disk or a hasher
+//! without buffering it.
+
+use std::io::{self, BufRead, Read};
+
+use crate::{parse_head, CaseString, WarcError, WarcRecord};
+use std::collections::HashMap;
+
+/// The `version` and `header` portion of a record, read ahead of its content
+///
+/// Returned alongside a [StreamingContent] by [WarcRecord::parse_streaming](crate::WarcRecord::parse_streaming).
+pub struct WarcRecordHead {
+    /// WARC version string (WARC/1.1)
+    pub version: String,
+    /// Record header fields
+    pub header: HashMap<CaseString, String>,
+}
+
+/// A record's content block, read lazily from the underlying reader
+///
+/// Implements [Read], yielding at most `Content-Length` bytes. Once fully
+/// drained it consumes the mandatory trailing `\r\n\r\n` delimiter itself, so
+/// the underlying reader is left positioned at the start of the next record
+/// and `sum` reflects the whole record, exactly as after [WarcRecord::parse](crate::WarcRecord::parse).
+pub struct StreamingContent<'r, R> {
+    read: &'r mut R,
+    sum: &'r mut usize,
+    remaining: usize,
+    finished: bool,
+}
+
+impl<'r, R: BufRead> Read for StreamingContent<'r, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            if !self.finished {
+                self.finished = true;
+
+                let mut linefeed = [0u8; 4];
+                self.read.read_exact(&mut linefeed)?;
+                *self.sum += 4;
+
+                if linefeed != [13, 10, 13, 10] {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "No double linefeed after record content",
+                    ));
+                }
+            }
+
+            return Ok(0);
+        }
+
+        let want = buf.len().min(self.remaining);
+        let n = self.read.read(&mut buf[..want])?;
+
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Content-Length exceeds available data",
+            ));
+        }
+
+        self.remaining -= n;
+        *self.sum += n;
+
+        Ok(n)
+    }
+}
+
+impl WarcRecord {
+    /// Parse a record's `version` and headers, then hand back its content as a
+    /// lazily-read [StreamingContent] instead of buffering it into a `Vec<u8>`
+    pub fn parse_streaming<'r, R: BufRead>(
+        read: &'r mut R,
+        sum: &'r mut usize,
+    ) -> Result<(WarcRecordHead, StreamingContent<'r, R>), WarcError> {
+        let (version, header, content_len) = parse_head(read, sum)?;
+
+        Ok((
+            WarcRecordHead { version, header },
+            StreamingContent {
+                read,
+                sum,
+                remaining: content_len,
+                finished: false,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_content_and_advances_past_delimiter() {
+        let mut data = &include_bytes!("test.warc")[..];
+        let mut sum = 0;
+        let (head, mut content) = WarcRecord::parse_streaming(&mut data, &mut sum).unwrap();
+
+        assert_eq!(head.version, "WARC/1.1");
+
+        let mut buf = Vec::new();
+        content.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"test");
+
+        // the whole record, trailing `\r\n\r\n` included, has been consumed
+        assert_eq!(sum, include_bytes!("test.warc").len());
+    }
+
+    #[test]
+    fn advances_reader_to_next_record_after_draining() {
+        let mut data = &include_bytes!("warc.in")[..];
+        let mut sum = 0;
+
+        let (_head, mut content) = WarcRecord::parse_streaming(&mut data, &mut sum).unwrap();
+        let mut buf = Vec::new();
+        content.read_to_end(&mut buf).unwrap();
+        assert!(buf.is_empty());
+
+        let record = WarcRecord::parse(&mut data, &mut sum).unwrap();
+        assert_eq!(
+            record.header.get(&"WARC-Type".into()),
+            Some(&"request".into())
+        );
+    }
+
+    #[test]
+    fn errors_instead_of_truncating_when_content_length_overruns_the_data() {
+        // Content-Length claims 100 bytes but only 12 are actually present
+        let mut data = &b"WARC/1.1\r\nWARC-Type: response\r\nContent-Length: 100\r\n\r\nhello world!"[..];
+        let mut sum = 0;
+
+        let (_head, mut content) = WarcRecord::parse_streaming(&mut data, &mut sum).unwrap();
+        let mut buf = Vec::new();
+        let err = content.read_to_end(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,136 @@
+//! WARC record serialization
+//!
+//! [WarcWriter] mirrors [WarcRecord::parse](crate::WarcRecord::parse), writing a
+//! [WarcRecord](crate::WarcRecord) back out in the on-disk WARC format.
+
+use std::io::{self, Write};
+
+use crate::WarcRecord;
+
+/// WARC record writer
+///
+/// Serializes [WarcRecords](WarcRecord) to a [Write] output, the inverse of
+/// [WarcRecord::parse].
+///
+/// # Usage
+/// ```rust
+/// use rust_warc::{WarcRecord, WarcWriter};
+/// use std::collections::HashMap;
+///
+/// let record = WarcRecord {
+///     version: "WARC/1.1".to_string(),
+///     header: {
+///         let mut header = HashMap::new();
+///         header.insert("WARC-Type".into(), "warcinfo".to_string());
+///         header
+///     },
+///     content: b"test".to_vec(),
+/// };
+///
+/// let mut out = Vec::new();
+/// let mut writer = WarcWriter::new(&mut out);
+/// writer.write_record(&record).unwrap();
+/// ```
+pub struct WarcWriter<W> {
+    write: W,
+}
+
+impl<W: Write> WarcWriter<W> {
+    /// Create a new WarcWriter wrapping a [Write] output
+    pub fn new(write: W) -> Self {
+        Self { write }
+    }
+
+    /// Serialize a single record, returning the number of bytes written
+    ///
+    /// `Content-Length` is validated (or inserted, if absent) against
+    /// `record.content.len()` before anything is written, so a caller can't
+    /// produce a record with a mismatched length.
+    pub fn write_record(&mut self, record: &WarcRecord) -> io::Result<usize> {
+        let content_len = record.content.len();
+        match record.header.get(&"Content-Length".into()) {
+            Some(len) if len.parse::<usize>().ok() != Some(content_len) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Content-Length header does not match content.len()",
+                ));
+            }
+            _ => {}
+        }
+
+        let mut written = 0;
+
+        written += self.write_line(&record.version)?;
+
+        for (key, value) in record.header.iter() {
+            if key == &"Content-Length".to_string() {
+                continue;
+            }
+            written += self.write_header(key.as_original_str(), value)?;
+        }
+        written += self.write_header("Content-Length", &content_len.to_string())?;
+
+        self.write.write_all(b"\r\n")?;
+        written += 2;
+
+        self.write.write_all(&record.content)?;
+        written += record.content.len();
+
+        self.write.write_all(b"\r\n\r\n")?;
+        written += 4;
+
+        Ok(written)
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<usize> {
+        self.write.write_all(line.as_bytes())?;
+        self.write.write_all(b"\r\n")?;
+        Ok(line.len() + 2)
+    }
+
+    // re-expand a value that was folded across lines (joined on '\n' while
+    // parsing) back into RFC-style continuation lines
+    fn write_header(&mut self, key: &str, value: &str) -> io::Result<usize> {
+        let mut written = 0;
+
+        let mut lines = value.split('\n');
+        let first = lines.next().unwrap_or("");
+        written += self.write_line(&format!("{}: {}", key, first))?;
+
+        for cont in lines {
+            written += self.write_line(&format!(" {}", cont))?;
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WarcRecord;
+
+    #[test]
+    fn round_trips_through_parse() {
+        let mut data = &include_bytes!("test.warc")[..];
+        let record = WarcRecord::parse(&mut data, &mut 0).unwrap();
+
+        let mut out = Vec::new();
+        WarcWriter::new(&mut out).write_record(&record).unwrap();
+
+        // canonical header casing is preserved, not forced to lower-case
+        let rewritten = String::from_utf8(out.clone()).unwrap();
+        assert!(rewritten.contains("WARC-Type: warcinfo"));
+        assert!(rewritten.contains("Content-Type: text/plain"));
+
+        let mut reparsed = &out[..];
+        let reparsed = WarcRecord::parse(&mut reparsed, &mut 0).unwrap();
+
+        assert_eq!(reparsed.version, record.version);
+        assert_eq!(reparsed.content, record.content);
+        assert_eq!(
+            reparsed.header.get(&"content-type".into()),
+            Some(&"text/plain".into())
+        );
+    }
+}